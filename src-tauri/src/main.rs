@@ -23,7 +23,11 @@ mod commands;
 // Import command functions
 use commands::{
     ProcessState,
+    CommandInfo,
+    CommandRegistry,
+    list_commands,
     greet,
+    set_ascii_logging,
     debug_folder_structure,
     get_files_in_directory,
     get_folders_in_directory, 
@@ -37,17 +41,81 @@ use commands::{
     read_excel_column,
     create_pdf,
     create_pdf_from_images,
+    create_contact_sheet,
     get_pdf_subfolders,
     copy_file_to_all_subfolders,
     change_pdf_dates,
     merge_pdf_files,
     sort_files_by_folders,
+    anonymize_folders,
+    deanonymize_folders,
+    match_files_to_excel,
+    fix_double_extensions,
+    rename_by_ocr,
+    apply_operations,
+    detect_excel_column,
+    audit_rename,
+    tidy_names,
+    merge_pdfs_with_separators,
+    summarize_folder,
+    stamp_page_numbers,
+    stamp_page_numbers_in_folder,
+    validate_excel_names,
+    convert_images,
+    group_files_by_regex,
+    preview_pdf_layout,
+    clean_filenames,
+    get_sort_alphabet,
+    detect_data_start,
+    preview_deletions,
+    merge_images_and_pdfs,
+    dedupe_excel_names,
+    quarantine_invalid_images,
+    restore_quarantine,
+    test_rename_rule,
+    delete_matching,
+    create_pdf_preview,
+    snapshot_tree,
+    diff_snapshot,
+    interleave_folders,
+    pdf_info,
+    set_file_dates,
+    verify_ordering,
+    change_extension,
+    prefix_with_parent,
+    extract_pdf_text,
+    export_name_template,
+    rename_from_template,
+    set_debug_console,
+    validate_regex_rename,
+    merge_selected_folders,
+    preview_tidy,
+    apply_tidy,
+    rename_files_by_fuzzy_key,
+    detect_mislabeled_files,
+    replace_text_in_pdfs,
+    save_profile,
+    load_profile,
+    list_profiles,
+    sort_position,
+    get_pdf_outline,
     pause_process,
     resume_process,
     stop_process,
     get_process_status
 };
 
+/// Declares the Tauri commands grouped by capability and expands to the invoke handler
+/// together with the matching CommandInfo list, so the two can never drift apart
+macro_rules! command_registry {
+    ($($group:literal => [$($command:ident),* $(,)?]),* $(,)?) => {
+        (
+            tauri::generate_handler![$($($command),*),*],
+            vec![$($(CommandInfo { name: stringify!($command), group: $group }),*),*],
+        )
+    };
+}
+
 /**
  * Application entry point
  * 
@@ -55,55 +123,126 @@ use commands::{
  * and starts the main event loop.
  */
 fn main() {
-    // Configure and build the Tauri application
-    let app = tauri::Builder::default()
-        .manage(ProcessState::new())
-        .invoke_handler(tauri::generate_handler![
-            // Basic utilities
+    // Optional ASCII-only console logging for non-UTF-8 consoles
+    commands::configure_logging_from_env();
+    
+    // Every command is declared once: the invoke handler and list_commands both come from here
+    let (command_handler, registered_commands) = command_registry! {
+        "Basic utilities" => [
             greet,
+            list_commands,
             debug_folder_structure,
-            
-            // File system operations
+            set_ascii_logging,
+            get_sort_alphabet,
+            set_debug_console,
+        ],
+        "File system operations" => [
             get_files_in_directory,
             get_folders_in_directory,
             get_folders_with_sorting,
             get_files_with_sorting,
-            
-            // Excel integration
+            summarize_folder,
+            quarantine_invalid_images,
+            restore_quarantine,
+            delete_matching,
+            snapshot_tree,
+            diff_snapshot,
+            interleave_folders,
+            set_file_dates,
+            sort_position,
+        ],
+        "Excel integration" => [
             read_excel_column,
-            
-            // Renaming operations
+            match_files_to_excel,
+            detect_excel_column,
+            audit_rename,
+            validate_excel_names,
+            detect_data_start,
+            dedupe_excel_names,
+            verify_ordering,
+        ],
+        "Renaming operations" => [
             rename_files,
             rename_folders,
             rename_folders_from_excel,
             rename_files_from_excel,
             rename_files_from_excel_advanced,
-            
-            // Document operations
+            fix_double_extensions,
+            rename_by_ocr,
+            tidy_names,
+            clean_filenames,
+            test_rename_rule,
+            change_extension,
+            prefix_with_parent,
+            export_name_template,
+            rename_from_template,
+            validate_regex_rename,
+            preview_tidy,
+            apply_tidy,
+            rename_files_by_fuzzy_key,
+            detect_mislabeled_files,
+        ],
+        "Document operations" => [
             create_pdf,
-            
-            // PDF creation operations
+            extract_pdf_text,
+        ],
+        "PDF creation operations" => [
             create_pdf_from_images,
+            create_contact_sheet,
             get_pdf_subfolders,
-            
-            // File copy operations
+            convert_images,
+            preview_pdf_layout,
+            preview_deletions,
+            create_pdf_preview,
+        ],
+        "File copy operations" => [
             copy_file_to_all_subfolders,
-            
-            // PDF date change operations
+        ],
+        "PDF date change operations" => [
             change_pdf_dates,
-            
-            // PDF merger operations
+            replace_text_in_pdfs,
+        ],
+        "PDF merger operations" => [
             merge_pdf_files,
-            
-            // File sorter operations
+            merge_pdfs_with_separators,
+            merge_images_and_pdfs,
+            pdf_info,
+            merge_selected_folders,
+            get_pdf_outline,
+        ],
+        "File sorter operations" => [
             sort_files_by_folders,
-            
-            // Process control operations
+            group_files_by_regex,
+        ],
+        "Folder anonymization operations" => [
+            anonymize_folders,
+            deanonymize_folders,
+        ],
+        "Batch operations" => [
+            apply_operations,
+        ],
+        "PDF page numbering operations" => [
+            stamp_page_numbers,
+            stamp_page_numbers_in_folder,
+        ],
+        "Profile operations" => [
+            save_profile,
+            load_profile,
+            list_profiles,
+        ],
+        "Process control operations" => [
             pause_process,
             resume_process,
             stop_process,
-            get_process_status
-        ])
+            get_process_status,
+        ],
+    };
+    
+    // Configure and build the Tauri application
+    let app = tauri::Builder::default()
+        .manage(ProcessState::new())
+        .manage(CommandRegistry(registered_commands))
+        .invoke_handler(command_handler)
         .build(tauri::generate_context!());
 
     // Handle application startup errors